@@ -0,0 +1,53 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const BASE_DELAY: Duration = Duration::from_secs(1);
+const MAX_DELAY: Duration = Duration::from_secs(300);
+
+///
+/// Exponential backoff with jitter, bounded by a maximum number of
+/// attempts. Delays double each attempt starting from `BASE_DELAY`, capped
+/// at `MAX_DELAY`, with up to 50% random jitter added to avoid synchronized
+/// reconnect storms.
+///
+pub struct Backoff {
+    max_retries: u32,
+    attempt: u32,
+}
+
+impl Backoff {
+    pub fn new(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            attempt: 0,
+        }
+    }
+
+    /// Returns the delay to wait before the next attempt, or `None` once
+    /// `max_retries` has been exhausted.
+    pub fn next_delay(&mut self) -> Option<Duration> {
+        if self.attempt >= self.max_retries {
+            return None;
+        }
+
+        self.attempt += 1;
+        // attempt 1 -> BASE_DELAY (1s), doubling each attempt after that;
+        // the exponent is allowed past the point where it would exceed
+        // MAX_DELAY so the cap is actually reachable, not just approached.
+        let exponent = (self.attempt - 1).min(9);
+        let unjittered = (BASE_DELAY * (1u32 << exponent)).min(MAX_DELAY);
+        let jittered = unjittered.mul_f64(1.0 + jitter_fraction() * 0.5);
+        Some(jittered.min(MAX_DELAY))
+    }
+
+    pub fn attempt(&self) -> u32 {
+        self.attempt
+    }
+}
+
+fn jitter_fraction() -> f64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .subsec_nanos();
+    (nanos % 1_000) as f64 / 1_000.0
+}
@@ -0,0 +1,56 @@
+use std::{fs, io::ErrorKind, path::PathBuf};
+
+use anyhow::Result;
+use btleplug::api::BDAddr;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Directory resume cursors are stored in, one JSON file per `whoop_addr`.
+/// Stands in for a `sync_cursors` table until the `openwhoop` library crate
+/// (outside this checkout) grows one with a migration.
+const CURSOR_DIR: &str = "sync_cursors";
+
+///
+/// The last packet id a `watch` cycle has synced up to for a given device,
+/// so a restart resumes from there instead of re-scanning from packet 0.
+///
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct SyncCursor {
+    pub last_packet_id: i32,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Default for SyncCursor {
+    fn default() -> Self {
+        Self {
+            last_packet_id: 0,
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+/// Loads the persisted cursor for `whoop_addr`, or the default (start from
+/// packet 0) if none has been saved yet.
+pub fn load(whoop_addr: BDAddr) -> Result<SyncCursor> {
+    match fs::read_to_string(cursor_path(whoop_addr)) {
+        Ok(contents) => Ok(serde_json::from_str(&contents)?),
+        Err(error) if error.kind() == ErrorKind::NotFound => Ok(SyncCursor::default()),
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// Persists `last_packet_id` as the resume cursor for `whoop_addr`.
+pub fn save(whoop_addr: BDAddr, last_packet_id: i32) -> Result<()> {
+    let cursor = SyncCursor {
+        last_packet_id,
+        updated_at: Utc::now(),
+    };
+
+    fs::create_dir_all(CURSOR_DIR)?;
+    fs::write(cursor_path(whoop_addr), serde_json::to_string(&cursor)?)?;
+    Ok(())
+}
+
+fn cursor_path(whoop_addr: BDAddr) -> PathBuf {
+    PathBuf::from(CURSOR_DIR).join(format!("{}.json", whoop_addr.to_string().replace(':', "-")))
+}
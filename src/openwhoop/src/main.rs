@@ -1,6 +1,15 @@
 #[macro_use]
 extern crate log;
 
+mod backoff;
+mod cursor;
+mod db_backend;
+mod server;
+mod sync_job;
+mod watch;
+mod worker;
+mod workers;
+
 use std::time::Duration;
 
 use anyhow::anyhow;
@@ -15,8 +24,18 @@ use openwhoop::{
     types::activities::{ActivityType, SearchActivityPeriods},
     DatabaseHandler, OpenWhoop, WhoopDevice,
 };
+use backoff::Backoff;
+use db_backend::DbBackend;
+use server::serve_command;
+use sync_job::{tracked_sync_history, SyncJobKind};
 use tokio::time::sleep;
+use watch::{watch_command, Tranquility};
 use whoop::{constants::WHOOP_SERVICE, WhoopPacket};
+use worker::{render_status_table, WorkerManager};
+use workers::{
+    connect_whoop_device, EventDetectionWorker, HistorySyncWorker, ScanConnectWorker,
+    StressCalcWorker,
+};
 
 #[derive(Parser)]
 pub struct OpenWhoopCli {
@@ -40,6 +59,9 @@ pub enum OpenWhoopCommand {
     DownloadHistory {
         #[arg(long, env)]
         whoop_addr: BDAddr,
+        /// Maximum number of reconnect attempts before giving up
+        #[arg(long, env, default_value_t = 10)]
+        max_retries: u32,
     },
     ///
     /// Reruns the packet processing on stored packets
@@ -62,6 +84,53 @@ pub enum OpenWhoopCommand {
     /// Calculate stress for historical data
     ///
     CalculateStress,
+    ///
+    /// Runs device scan/connect, history sync, event detection and stress
+    /// calculation as supervised background workers until interrupted
+    ///
+    Daemon {
+        #[arg(long, env)]
+        whoop_addr: BDAddr,
+    },
+    ///
+    /// Runs the same background workers as `daemon`, printing a live table
+    /// of their state so you can see what's running, idle, or dead and why
+    ///
+    Workers {
+        #[arg(long, env)]
+        whoop_addr: BDAddr,
+    },
+    ///
+    /// Re-connects to a known whoop device on a schedule, incrementally
+    /// syncing history and running detection, resuming from the last
+    /// persisted cursor instead of starting over
+    ///
+    Watch {
+        #[arg(long, env)]
+        whoop_addr: BDAddr,
+        /// Idle seconds between watch cycles
+        #[arg(long, env, default_value_t = 300)]
+        tranquility: u64,
+    },
+    ///
+    /// Starts a local JSON-RPC/HTTP server exposing sleep and exercise
+    /// stats, and methods to trigger a history sync, event detection or a
+    /// stress calculation on demand; no stress values are returned
+    ///
+    Serve {
+        #[arg(long, env)]
+        whoop_addr: BDAddr,
+        #[arg(long, env, default_value = "127.0.0.1")]
+        host_v4: std::net::Ipv4Addr,
+        #[arg(long, env, default_value = "::1")]
+        host_v6: std::net::Ipv6Addr,
+        #[arg(long, env, default_value_t = 8787)]
+        port: u16,
+        /// Initial idle seconds between the background watch cycles; adjustable
+        /// at runtime via the `set_tranquility` RPC method
+        #[arg(long, env, default_value_t = 300)]
+        tranquility: u64,
+    },
 }
 
 #[tokio::main]
@@ -76,6 +145,9 @@ async fn main() -> anyhow::Result<()> {
         .init();
 
     let cli = OpenWhoopCli::parse();
+    let backend = DbBackend::detect(&cli.database_url)?;
+    info!("connecting to {} backend", backend);
+    backend.validate(&cli.database_url).await?;
     let db_handler = DatabaseHandler::new(cli.database_url).await;
 
     let manager = Manager::new().await?;
@@ -107,27 +179,49 @@ async fn main() -> anyhow::Result<()> {
             scan_command(adapter, None).await?;
             Ok(())
         }
-        OpenWhoopCommand::DownloadHistory { whoop_addr } => {
+        OpenWhoopCommand::DownloadHistory {
+            whoop_addr,
+            max_retries,
+        } => {
             let peripheral = scan_command(adapter, Some(whoop_addr)).await?;
-            let mut whoop = WhoopDevice::new(peripheral, db_handler);
+            let mut whoop = WhoopDevice::new(peripheral, db_handler.clone());
 
             whoop.connect().await?;
             whoop.initialize().await?;
 
-            let result = whoop.sync_history().await;
-            if let Err(e) = result {
+            let sync_result =
+                tracked_sync_history(&mut whoop, SyncJobKind::HistoryDownload).await;
+            if let Err(e) = sync_result {
                 error!("{}", e);
             }
 
+            let mut backoff = Backoff::new(max_retries);
             loop {
                 if let Ok(true) = whoop.is_connected().await {
                     whoop
                         .send_command(WhoopPacket::exit_high_freq_sync())
                         .await?;
                     break;
-                } else {
-                    whoop.connect().await?;
-                    sleep(Duration::from_secs(1)).await;
+                }
+
+                let Some(delay) = backoff.next_delay() else {
+                    let message = format!(
+                        "gave up reconnecting to {} after {} attempts",
+                        whoop_addr, max_retries
+                    );
+                    return Err(anyhow!(message));
+                };
+
+                warn!(
+                    "lost connection to {}, retrying in {:?} (attempt {}/{})",
+                    whoop_addr,
+                    delay,
+                    backoff.attempt(),
+                    max_retries
+                );
+                sleep(delay).await;
+                if let Err(error) = whoop.connect().await {
+                    warn!("reconnect to {} failed: {}", whoop_addr, error);
                 }
             }
 
@@ -207,10 +301,70 @@ async fn main() -> anyhow::Result<()> {
             whoop.calculate_stress().await?;
             Ok(())
         }
+        OpenWhoopCommand::Daemon { whoop_addr } => {
+            let manager = spawn_background_workers(adapter, whoop_addr, db_handler).await?;
+
+            loop {
+                sleep(Duration::from_secs(30)).await;
+                info!("\n{}", render_status_table(&manager.statuses().await));
+            }
+        }
+        OpenWhoopCommand::Workers { whoop_addr } => {
+            let manager = spawn_background_workers(adapter, whoop_addr, db_handler).await?;
+
+            loop {
+                let table = render_status_table(&manager.statuses().await);
+                print!("\x1B[2J\x1B[1;1H{}", table);
+                sleep(Duration::from_secs(2)).await;
+            }
+        }
+        OpenWhoopCommand::Watch {
+            whoop_addr,
+            tranquility,
+        } => {
+            let tranquility = Tranquility::new(tranquility);
+            watch_command(adapter, whoop_addr, db_handler, tranquility).await
+        }
+        OpenWhoopCommand::Serve {
+            whoop_addr,
+            host_v4,
+            host_v6,
+            port,
+            tranquility,
+        } => {
+            serve_command(
+                adapter, whoop_addr, host_v4, host_v6, port, tranquility, db_handler,
+            )
+            .await
+        }
     }
 }
 
-async fn scan_command(
+async fn spawn_background_workers(
+    adapter: Adapter,
+    whoop_addr: BDAddr,
+    db_handler: DatabaseHandler,
+) -> anyhow::Result<WorkerManager> {
+    let device = connect_whoop_device(adapter, whoop_addr, db_handler.clone()).await?;
+
+    let mut manager = WorkerManager::new();
+    manager
+        .spawn(Box::new(ScanConnectWorker::new(device.clone())))
+        .await;
+    manager
+        .spawn(Box::new(HistorySyncWorker::new(device)))
+        .await;
+    manager
+        .spawn(Box::new(EventDetectionWorker::new(db_handler.clone())))
+        .await;
+    manager
+        .spawn(Box::new(StressCalcWorker::new(db_handler)))
+        .await;
+
+    Ok(manager)
+}
+
+pub(crate) async fn scan_command(
     adapter: Adapter,
     peripheral_addr: Option<BDAddr>,
 ) -> anyhow::Result<Peripheral> {
@@ -0,0 +1,202 @@
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use anyhow::{anyhow, Result};
+use axum::{extract::State, routing::post, Json, Router};
+use btleplug::{api::BDAddr, platform::Adapter};
+use openwhoop::{
+    algo::{ExerciseMetrics, SleepConsistencyAnalyzer},
+    types::activities::{ActivityType, SearchActivityPeriods},
+    DatabaseHandler, OpenWhoop,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{
+    sync_job::{tracked_sync_history, SyncJobKind},
+    watch::{watch_command, Tranquility},
+    workers::connect_whoop_device,
+};
+
+#[derive(Clone)]
+struct ServerState {
+    db_handler: DatabaseHandler,
+    adapter: Adapter,
+    whoop_addr: BDAddr,
+    tranquility: Tranquility,
+}
+
+#[derive(Deserialize)]
+struct RpcRequest {
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Serialize)]
+struct RpcResponse {
+    result: Option<Value>,
+    error: Option<String>,
+}
+
+///
+/// Serves the stats printed by `sleep-stats` and `exercise-stats` as
+/// JSON-RPC methods over HTTP, plus methods to kick off a history download,
+/// event detection or a stress calculation on demand. `calculate_stress`
+/// only runs the calculation and returns a status; it returns no stress
+/// values. Listens on both
+/// `host_v4` and `host_v6` on the same port so IPv4 and IPv6 clients can
+/// share one endpoint.
+///
+/// Also runs a `watch` loop in the background so the server stays
+/// up to date without a separate process, with its tranquility throttle
+/// adjustable at runtime through the `set_tranquility` method.
+///
+pub async fn serve_command(
+    adapter: Adapter,
+    whoop_addr: BDAddr,
+    host_v4: Ipv4Addr,
+    host_v6: Ipv6Addr,
+    port: u16,
+    tranquility_seconds: u64,
+    db_handler: DatabaseHandler,
+) -> Result<()> {
+    let tranquility = Tranquility::new(tranquility_seconds);
+    tokio::spawn(watch_command(
+        adapter.clone(),
+        whoop_addr,
+        db_handler.clone(),
+        tranquility.clone(),
+    ));
+
+    let state = ServerState {
+        db_handler,
+        adapter,
+        whoop_addr,
+        tranquility,
+    };
+    let app = Router::new()
+        .route("/rpc", post(handle_rpc))
+        .with_state(state);
+
+    let addr_v4 = SocketAddr::new(host_v4.into(), port);
+    let addr_v6 = SocketAddr::new(host_v6.into(), port);
+
+    let listener_v4 = tokio::net::TcpListener::bind(addr_v4).await?;
+    let listener_v6 = tokio::net::TcpListener::bind(addr_v6).await?;
+
+    info!("serving JSON-RPC on {} and {}", addr_v4, addr_v6);
+
+    tokio::try_join!(
+        axum::serve(listener_v4, app.clone().into_make_service()),
+        axum::serve(listener_v6, app.into_make_service()),
+    )?;
+
+    Ok(())
+}
+
+async fn handle_rpc(
+    State(state): State<ServerState>,
+    Json(request): Json<RpcRequest>,
+) -> Json<RpcResponse> {
+    match dispatch(&state, &request.method, request.params).await {
+        Ok(value) => Json(RpcResponse {
+            result: Some(value),
+            error: None,
+        }),
+        Err(error) => Json(RpcResponse {
+            result: None,
+            error: Some(error.to_string()),
+        }),
+    }
+}
+
+async fn dispatch(state: &ServerState, method: &str, params: Value) -> Result<Value> {
+    let whoop = OpenWhoop::new(state.db_handler.clone());
+
+    match method {
+        "sleep_stats" => {
+            let sleep_records = whoop.database.get_sleep_cycles().await?;
+            let mut last_week = sleep_records
+                .iter()
+                .rev()
+                .take(7)
+                .copied()
+                .collect::<Vec<_>>();
+            last_week.reverse();
+
+            let all_time =
+                SleepConsistencyAnalyzer::new(sleep_records).calculate_consistency_metrics();
+            let week = SleepConsistencyAnalyzer::new(last_week).calculate_consistency_metrics();
+
+            Ok(json!({
+                "all_time": serde_json::to_value(&all_time)?,
+                "week": serde_json::to_value(&week)?,
+            }))
+        }
+        "exercise_stats" => {
+            let mut search = SearchActivityPeriods::default().with_activity(ActivityType::Activity);
+            if let (Some(start), Some(end)) = (
+                params.get("start").and_then(Value::as_str),
+                params.get("end").and_then(Value::as_str),
+            ) {
+                search = search.with_range(start.parse()?, end.parse()?);
+            }
+
+            let exercises = whoop.database.search_activities(search).await?;
+            let metrics = ExerciseMetrics::new(exercises);
+
+            Ok(json!({ "metrics": serde_json::to_value(&metrics)? }))
+        }
+        "calculate_stress" => {
+            whoop.calculate_stress().await?;
+            Ok(json!({ "status": "completed" }))
+        }
+        "set_tranquility" => {
+            let seconds = params
+                .get("seconds")
+                .and_then(Value::as_u64)
+                .ok_or_else(|| anyhow!("`seconds` param is required"))?;
+            state.tranquility.set(seconds);
+            Ok(json!({ "tranquility_seconds": seconds }))
+        }
+        "detect_events" => {
+            whoop.detect_sleeps().await?;
+            whoop.detect_events().await?;
+            Ok(json!({ "status": "completed" }))
+        }
+        "download_history" => {
+            let db_handler = state.db_handler.clone();
+            let adapter = state.adapter.clone();
+            let whoop_addr = state.whoop_addr;
+
+            tokio::spawn(async move {
+                let device = match connect_whoop_device(adapter, whoop_addr, db_handler).await {
+                    Ok(device) => device,
+                    Err(error) => {
+                        error!("download_history: failed to start: {}", error);
+                        return;
+                    }
+                };
+
+                let mut device = device.lock().await;
+                if let Err(error) = device.connect().await {
+                    error!("download_history: failed to connect: {}", error);
+                    return;
+                }
+                if let Err(error) = device.initialize().await {
+                    error!("download_history: failed to initialize: {}", error);
+                    return;
+                }
+
+                if let Err(error) =
+                    tracked_sync_history(&mut device, SyncJobKind::HistoryDownload).await
+                {
+                    error!("download_history: sync failed: {}", error);
+                }
+            });
+
+            Ok(json!({ "status": "started" }))
+        }
+        other => Err(anyhow!("unknown method `{}`", other)),
+    }
+}
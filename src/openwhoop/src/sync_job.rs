@@ -0,0 +1,99 @@
+use std::{
+    fs::OpenOptions,
+    io::Write as _,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use anyhow::Result;
+use chrono::Utc;
+use openwhoop::WhoopDevice;
+
+static NEXT_JOB_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Append-only log of sync-job attempts, relative to the process's working
+/// directory. Stands in for a `sync_jobs` table until the `openwhoop`
+/// library crate (outside this checkout) grows one with a migration.
+const SYNC_JOB_LOG: &str = "sync_jobs.log";
+
+///
+/// The kind of background job a [`SyncJob`] line in `sync_jobs.log`
+/// represents.
+///
+#[derive(Clone, Copy, Debug)]
+pub enum SyncJobKind {
+    HistoryDownload,
+    Watch,
+}
+
+impl SyncJobKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SyncJobKind::HistoryDownload => "history_download",
+            SyncJobKind::Watch => "watch",
+        }
+    }
+}
+
+///
+/// Tracks a single sync attempt's lifecycle as a line appended to
+/// `sync_jobs.log`, so sync history and failures are durable across
+/// restarts instead of living only in transient process logs.
+///
+pub struct SyncJob {
+    job_id: u64,
+    kind: SyncJobKind,
+}
+
+impl SyncJob {
+    pub fn start(kind: SyncJobKind) -> Result<Self> {
+        let job_id = NEXT_JOB_ID.fetch_add(1, Ordering::Relaxed);
+        let job = Self { job_id, kind };
+        job.append("started", None)?;
+        Ok(job)
+    }
+
+    pub fn succeeded(&self) -> Result<()> {
+        self.append("succeeded", None)
+    }
+
+    pub fn failed(&self, attempt: u32, error: &str) -> Result<()> {
+        self.append("failed", Some((attempt, error)))
+    }
+
+    fn append(&self, status: &str, failure: Option<(u32, &str)>) -> Result<()> {
+        let mut line = format!(
+            "{} job={} kind={} status={}",
+            Utc::now().to_rfc3339(),
+            self.job_id,
+            self.kind.as_str(),
+            status
+        );
+        if let Some((attempt, error)) = failure {
+            line.push_str(&format!(" attempt={} error={:?}", attempt, error));
+        }
+        line.push('\n');
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(SYNC_JOB_LOG)?;
+        file.write_all(line.as_bytes())?;
+        Ok(())
+    }
+}
+
+///
+/// Runs `sync_history` wrapped in a `SyncJob`, so every call site gets
+/// durable status tracking for free instead of re-deriving the same
+/// start/succeeded/failed bookkeeping at each one.
+///
+pub async fn tracked_sync_history(whoop: &mut WhoopDevice, kind: SyncJobKind) -> Result<()> {
+    let job = SyncJob::start(kind)?;
+    match whoop.sync_history().await {
+        Ok(()) => job.succeeded(),
+        Err(error) => {
+            job.failed(0, &error.to_string())?;
+            Err(error)
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+use anyhow::Result;
+use btleplug::{api::BDAddr, platform::Adapter};
+use openwhoop::{DatabaseHandler, OpenWhoop, WhoopDevice};
+use tokio::time::sleep;
+
+use crate::{
+    cursor,
+    scan_command,
+    sync_job::{tracked_sync_history, SyncJobKind},
+};
+
+///
+/// Shared, runtime-adjustable idle duration between `watch` cycles. Cloning
+/// shares the same underlying value, so any handle that updates it (e.g.
+/// the `serve` subcommand's `set_tranquility` RPC method) is immediately
+/// observed by the watch loop.
+///
+#[derive(Clone)]
+pub struct Tranquility(Arc<AtomicU64>);
+
+impl Tranquility {
+    pub fn new(seconds: u64) -> Self {
+        Self(Arc::new(AtomicU64::new(seconds)))
+    }
+
+    pub fn get(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.0.load(Ordering::Relaxed))
+    }
+
+    pub fn set(&self, seconds: u64) {
+        self.0.store(seconds, Ordering::Relaxed);
+    }
+}
+
+///
+/// Re-connects to `whoop_addr` on a schedule, incrementally syncs history,
+/// runs event/stress detection, and persists a resume cursor so a restart
+/// continues where it left off instead of re-scanning from packet id 0.
+///
+/// Runs forever; a cycle that fails (e.g. because the device is out of
+/// range) is logged and retried after the next `tranquility` sleep rather
+/// than aborting the watch.
+///
+pub async fn watch_command(
+    adapter: Adapter,
+    whoop_addr: BDAddr,
+    db_handler: DatabaseHandler,
+    tranquility: Tranquility,
+) -> Result<()> {
+    let whoop_events = OpenWhoop::new(db_handler.clone());
+
+    loop {
+        match run_watch_cycle(adapter.clone(), whoop_addr, &db_handler, &whoop_events).await {
+            Ok(synced) => info!("watch: synced {} packets from {}", synced, whoop_addr),
+            Err(error) => warn!(
+                "watch: cycle failed, device may be out of range: {}",
+                error
+            ),
+        }
+
+        sleep(tranquility.get()).await;
+    }
+}
+
+/// Fetches the resume cursor, runs one sync/detect cycle and persists the
+/// updated cursor. A failure anywhere in here (including reading or
+/// writing the cursor file itself) is returned to the caller, which logs
+/// it instead of propagating it, so one bad cycle doesn't kill the watch loop.
+async fn run_watch_cycle(
+    adapter: Adapter,
+    whoop_addr: BDAddr,
+    db_handler: &DatabaseHandler,
+    whoop_events: &OpenWhoop,
+) -> Result<usize> {
+    let saved_cursor = cursor::load(whoop_addr)?;
+
+    let (synced, last_packet_id) = run_cycle(
+        adapter,
+        whoop_addr,
+        db_handler,
+        whoop_events,
+        saved_cursor.last_packet_id,
+    )
+    .await?;
+
+    cursor::save(whoop_addr, last_packet_id)?;
+
+    Ok(synced)
+}
+
+async fn run_cycle(
+    adapter: Adapter,
+    whoop_addr: BDAddr,
+    db_handler: &DatabaseHandler,
+    whoop_events: &OpenWhoop,
+    since_packet_id: i32,
+) -> Result<(usize, i32)> {
+    let peripheral = scan_command(adapter, Some(whoop_addr)).await?;
+    let mut whoop = WhoopDevice::new(peripheral, db_handler.clone());
+
+    whoop.connect().await?;
+    whoop.initialize().await?;
+    tracked_sync_history(&mut whoop, SyncJobKind::Watch).await?;
+
+    let packets = db_handler.get_packets(since_packet_id).await?;
+    let last_packet_id = packets.last().map(|p| p.id).unwrap_or(since_packet_id);
+
+    whoop_events.detect_sleeps().await?;
+    whoop_events.detect_events().await?;
+    whoop_events.calculate_stress().await?;
+
+    Ok((packets.len(), last_packet_id))
+}
@@ -0,0 +1,196 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use tokio::sync::Mutex;
+
+/// Minimum wait after a failed `step`, so a worker that fails immediately
+/// (e.g. it can't find the device yet) backs off instead of busy-looping.
+const ERROR_BACKOFF: Duration = Duration::from_secs(5);
+
+///
+/// The result of a single step of work performed by a [`Worker`].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WorkerState {
+    /// The worker did useful work and should be stepped again immediately.
+    Active,
+    /// The worker has nothing to do right now and should sleep for `wait`
+    /// before being stepped again.
+    Idle { wait: Duration },
+    /// The worker has permanently stopped and should not be stepped again.
+    Dead,
+}
+
+impl fmt::Display for WorkerState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorkerState::Active => write!(f, "active"),
+            WorkerState::Idle { wait } => write!(f, "idle ({:?})", wait),
+            WorkerState::Dead => write!(f, "dead"),
+        }
+    }
+}
+
+///
+/// A single unit of background work supervised by a [`WorkerManager`].
+///
+/// Implementors should do a bounded amount of work per call to `step` and
+/// report back whether there is more work to do right away, or whether the
+/// manager should let the worker sleep for a while.
+///
+pub trait Worker: Send {
+    /// A short, stable name used to identify the worker in logs and status output.
+    fn name(&self) -> &str;
+
+    fn step(&mut self) -> Pin<Box<dyn Future<Output = anyhow::Result<WorkerState>> + Send + '_>>;
+}
+
+///
+/// A snapshot of a worker's health, as tracked by the [`WorkerManager`].
+///
+#[derive(Clone, Debug)]
+pub struct WorkerStatus {
+    pub state: WorkerState,
+    pub last_run: Option<Instant>,
+    pub iterations: u64,
+    pub last_error: Option<String>,
+}
+
+impl WorkerStatus {
+    fn new() -> Self {
+        Self {
+            state: WorkerState::Idle {
+                wait: Duration::ZERO,
+            },
+            last_run: None,
+            iterations: 0,
+            last_error: None,
+        }
+    }
+}
+
+type StatusMap = Arc<Mutex<HashMap<String, WorkerStatus>>>;
+
+///
+/// Owns a registry of background [`Worker`]s, spawns each on its own Tokio
+/// task and keeps track of their state so it can be inspected while the
+/// daemon is running.
+///
+/// A flaky worker never brings down the others: errors returned from `step`
+/// are logged and recorded on the worker's status instead of propagating.
+///
+pub struct WorkerManager {
+    statuses: StatusMap,
+}
+
+impl WorkerManager {
+    pub fn new() -> Self {
+        Self {
+            statuses: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    ///
+    /// Registers `worker` and spawns it onto its own Tokio task. The task
+    /// loops calling `step`, sleeping for the returned idle duration when
+    /// there is no work, and exits once the worker reports `Dead`.
+    ///
+    pub async fn spawn(&mut self, mut worker: Box<dyn Worker>) {
+        let name = worker.name().to_string();
+        self.statuses
+            .lock()
+            .await
+            .insert(name.clone(), WorkerStatus::new());
+
+        let statuses = self.statuses.clone();
+        tokio::spawn(async move {
+            loop {
+                let result = worker.step().await;
+                let mut statuses = statuses.lock().await;
+                let status = statuses
+                    .entry(name.clone())
+                    .or_insert_with(WorkerStatus::new);
+
+                status.last_run = Some(Instant::now());
+
+                match result {
+                    Ok(state @ WorkerState::Active) => {
+                        status.state = state;
+                        status.iterations += 1;
+                        status.last_error = None;
+                    }
+                    Ok(state @ WorkerState::Idle { .. }) => {
+                        status.state = state;
+                        status.iterations += 1;
+                        status.last_error = None;
+                    }
+                    Ok(WorkerState::Dead) => {
+                        status.state = WorkerState::Dead;
+                        drop(statuses);
+                        break;
+                    }
+                    Err(error) => {
+                        error!("worker `{}` failed: {}", name, error);
+                        status.last_error = Some(error.to_string());
+                        status.state = WorkerState::Idle {
+                            wait: ERROR_BACKOFF,
+                        };
+                    }
+                }
+
+                let wait = match status.state {
+                    WorkerState::Idle { wait } => wait,
+                    WorkerState::Active => Duration::from_millis(0),
+                    WorkerState::Dead => Duration::ZERO,
+                };
+                drop(statuses);
+
+                if wait > Duration::ZERO {
+                    tokio::time::sleep(wait).await;
+                }
+            }
+        });
+    }
+
+    /// Returns a snapshot of every worker's current status.
+    pub async fn statuses(&self) -> HashMap<String, WorkerStatus> {
+        self.statuses.lock().await.clone()
+    }
+}
+
+///
+/// Renders a worker registry snapshot as a simple table, for the `Workers`
+/// subcommand and the daemon's own status logging.
+///
+pub fn render_status_table(statuses: &HashMap<String, WorkerStatus>) -> String {
+    let mut names: Vec<&String> = statuses.keys().collect();
+    names.sort();
+
+    let mut out = String::new();
+    out.push_str(&format!(
+        "{:<20} {:<20} {:<12} {:<10} {}\n",
+        "WORKER", "STATE", "LAST RUN", "RUNS", "LAST ERROR"
+    ));
+
+    for name in names {
+        let status = &statuses[name];
+        let last_run = match status.last_run {
+            Some(instant) => format!("{:.1}s ago", instant.elapsed().as_secs_f32()),
+            None => "never".to_string(),
+        };
+        let last_error = status.last_error.as_deref().unwrap_or("-");
+
+        out.push_str(&format!(
+            "{:<20} {:<20} {:<12} {:<10} {}\n",
+            name, status.state, last_run, status.iterations, last_error
+        ));
+    }
+
+    out
+}
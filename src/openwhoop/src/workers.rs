@@ -0,0 +1,176 @@
+use std::{future::Future, pin::Pin, sync::Arc, time::Duration};
+
+use anyhow::anyhow;
+use btleplug::{
+    api::{BDAddr, Peripheral as _},
+    platform::{Adapter, Peripheral},
+};
+use openwhoop::{DatabaseHandler, OpenWhoop, WhoopDevice};
+use tokio::sync::Mutex;
+
+use crate::{
+    scan_command,
+    sync_job::{tracked_sync_history, SyncJobKind},
+    worker::{Worker, WorkerState},
+};
+
+const RECONNECT_WAIT: Duration = Duration::from_secs(5);
+const CONNECTED_POLL_WAIT: Duration = Duration::from_secs(30);
+const DETECTION_INTERVAL: Duration = Duration::from_secs(10 * 60);
+const STRESS_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+///
+/// Shared handle to the whoop device connection, used by every worker that
+/// needs to talk to the device itself.
+///
+pub type SharedWhoopDevice = Arc<Mutex<WhoopDevice>>;
+
+pub async fn connect_whoop_device(
+    adapter: Adapter,
+    whoop_addr: BDAddr,
+    db_handler: DatabaseHandler,
+) -> anyhow::Result<SharedWhoopDevice> {
+    let peripheral: Peripheral = scan_command(adapter, Some(whoop_addr)).await?;
+    let whoop = WhoopDevice::new(peripheral, db_handler);
+    Ok(Arc::new(Mutex::new(whoop)))
+}
+
+///
+/// Keeps the BLE connection to the whoop device alive, reconnecting whenever
+/// it drops.
+///
+pub struct ScanConnectWorker {
+    device: SharedWhoopDevice,
+}
+
+impl ScanConnectWorker {
+    pub fn new(device: SharedWhoopDevice) -> Self {
+        Self { device }
+    }
+}
+
+impl Worker for ScanConnectWorker {
+    fn name(&self) -> &str {
+        "scan_connect"
+    }
+
+    fn step(&mut self) -> Pin<Box<dyn Future<Output = anyhow::Result<WorkerState>> + Send + '_>> {
+        Box::pin(async move {
+            let mut whoop = self.device.lock().await;
+            if whoop.is_connected().await.unwrap_or(false) {
+                return Ok(WorkerState::Idle {
+                    wait: CONNECTED_POLL_WAIT,
+                });
+            }
+
+            whoop.connect().await?;
+            whoop.initialize().await?;
+            Ok(WorkerState::Active)
+        })
+    }
+}
+
+///
+/// Periodically pulls any newly buffered history off the device.
+///
+pub struct HistorySyncWorker {
+    device: SharedWhoopDevice,
+}
+
+impl HistorySyncWorker {
+    pub fn new(device: SharedWhoopDevice) -> Self {
+        Self { device }
+    }
+}
+
+impl Worker for HistorySyncWorker {
+    fn name(&self) -> &str {
+        "history_sync"
+    }
+
+    fn step(&mut self) -> Pin<Box<dyn Future<Output = anyhow::Result<WorkerState>> + Send + '_>> {
+        Box::pin(async move {
+            let mut whoop = self.device.lock().await;
+            if !whoop.is_connected().await.unwrap_or(false) {
+                return Ok(WorkerState::Idle {
+                    wait: RECONNECT_WAIT,
+                });
+            }
+
+            tracked_sync_history(&mut whoop, SyncJobKind::HistoryDownload).await?;
+            Ok(WorkerState::Idle {
+                wait: CONNECTED_POLL_WAIT,
+            })
+        })
+    }
+}
+
+///
+/// Periodically runs sleep/exercise event detection over stored packets.
+///
+pub struct EventDetectionWorker {
+    whoop: OpenWhoop,
+}
+
+impl EventDetectionWorker {
+    pub fn new(db_handler: DatabaseHandler) -> Self {
+        Self {
+            whoop: OpenWhoop::new(db_handler),
+        }
+    }
+}
+
+impl Worker for EventDetectionWorker {
+    fn name(&self) -> &str {
+        "event_detection"
+    }
+
+    fn step(&mut self) -> Pin<Box<dyn Future<Output = anyhow::Result<WorkerState>> + Send + '_>> {
+        Box::pin(async move {
+            self.whoop
+                .detect_sleeps()
+                .await
+                .map_err(|e| anyhow!(e.to_string()))?;
+            self.whoop
+                .detect_events()
+                .await
+                .map_err(|e| anyhow!(e.to_string()))?;
+            Ok(WorkerState::Idle {
+                wait: DETECTION_INTERVAL,
+            })
+        })
+    }
+}
+
+///
+/// Periodically recalculates stress over stored packets.
+///
+pub struct StressCalcWorker {
+    whoop: OpenWhoop,
+}
+
+impl StressCalcWorker {
+    pub fn new(db_handler: DatabaseHandler) -> Self {
+        Self {
+            whoop: OpenWhoop::new(db_handler),
+        }
+    }
+}
+
+impl Worker for StressCalcWorker {
+    fn name(&self) -> &str {
+        "stress_calc"
+    }
+
+    fn step(&mut self) -> Pin<Box<dyn Future<Output = anyhow::Result<WorkerState>> + Send + '_>> {
+        Box::pin(async move {
+            self.whoop
+                .calculate_stress()
+                .await
+                .map_err(|e| anyhow!(e.to_string()))?;
+            Ok(WorkerState::Idle {
+                wait: STRESS_INTERVAL,
+            })
+        })
+    }
+}